@@ -0,0 +1,142 @@
+//! Caches fixture compilation across generated tests that share a fixture
+//! (a manifest can declare several invocations against the same fixture),
+//! so a multi-case fixture only pays to compile its component once.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{bail, Result};
+use camino::Utf8PathBuf;
+use tempdir::TempDir;
+use xshell::Shell;
+
+use crate::compile;
+
+#[derive(Clone)]
+struct Compiled {
+    wasi_file: Utf8PathBuf,
+    // Never actually read, just kept alive: dropping it would delete the
+    // directory `wasi_file` points into. `Arc` (rather than a bare `TempDir`)
+    // only exists to make `Compiled` cheaply `Clone`; since this lives
+    // inside a process `static`, it's never dropped and is left for the
+    // OS's normal temp-dir cleanup to reclaim either way.
+    _dir: Arc<TempDir>,
+}
+
+/// Maps a key to a slot that's computed at most once. The outer mutex is
+/// only ever held long enough to look up or insert a slot, never while
+/// actually running `compute`, so computing key A doesn't block a
+/// concurrent thread computing unrelated key B; two threads racing on the
+/// *same* key share one slot, and the second simply waits for the first to
+/// finish via `OnceLock::get_or_init`.
+type Slots<T> = Mutex<HashMap<String, Arc<OnceLock<Result<T, String>>>>>;
+
+struct Cache<T> {
+    slots: OnceLock<Slots<T>>,
+}
+
+impl<T: Clone> Cache<T> {
+    const fn new() -> Self {
+        Cache {
+            slots: OnceLock::new(),
+        }
+    }
+
+    fn get_or_compute(&self, key: &str, compute: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+        let slot = {
+            let slots = self.slots.get_or_init(|| Mutex::new(HashMap::new()));
+            let mut slots = slots.lock().unwrap();
+            slots
+                .entry(key.to_string())
+                .or_insert_with(|| Arc::new(OnceLock::new()))
+                .clone()
+        };
+
+        slot.get_or_init(compute).clone()
+    }
+}
+
+static CACHE: Cache<Compiled> = Cache::new();
+
+/// Compiles the WASI fixture named `file_name`, reusing a previous
+/// compilation of the same fixture from elsewhere in this test binary
+/// instead of recompiling it.
+pub fn compile_cached(sh: &Shell, file_name: &str) -> Result<Utf8PathBuf> {
+    let result = CACHE.get_or_compute(file_name, || {
+        TempDir::new(file_name)
+            .map_err(|err| err.to_string())
+            .and_then(|dir| {
+                compile(sh, &dir, file_name)
+                    .map(|wasi_file| Compiled {
+                        wasi_file,
+                        _dir: Arc::new(dir),
+                    })
+                    .map_err(|err| err.to_string())
+            })
+    });
+
+    match result {
+        Ok(compiled) => Ok(compiled.wasi_file),
+        Err(message) => bail!("failed to compile fixture `{file_name}`: {message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn concurrent_calls_for_the_same_key_compute_only_once() {
+        let cache = Cache::<u32>::new();
+        let calls = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        cache.get_or_compute("same-key", || {
+                            calls.fetch_add(1, Ordering::SeqCst);
+                            // Give other threads a chance to race in before
+                            // this one finishes computing.
+                            std::thread::sleep(std::time::Duration::from_millis(20));
+                            Ok(42)
+                        })
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                assert_eq!(handle.join().unwrap(), Ok(42));
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn different_keys_compute_independently() {
+        let cache = Cache::<u32>::new();
+
+        let a = cache.get_or_compute("a", || Ok(1));
+        let b = cache.get_or_compute("b", || Ok(2));
+
+        assert_eq!(a, Ok(1));
+        assert_eq!(b, Ok(2));
+    }
+
+    #[test]
+    fn a_failed_compute_is_cached_too() {
+        let cache = Cache::<u32>::new();
+        let calls = AtomicUsize::new(0);
+
+        let compute = || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err("boom".to_string())
+        };
+
+        assert_eq!(cache.get_or_compute("key", compute), Err("boom".to_string()));
+        assert_eq!(cache.get_or_compute("key", compute), Err("boom".to_string()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
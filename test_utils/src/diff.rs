@@ -0,0 +1,84 @@
+//! A small line-oriented diff, used to show *why* a snapshot mismatched
+//! instead of just that it did.
+
+/// Computes a Myers/LCS line diff between `old` and `new`, returning one
+/// entry per line tagged `' '` (unchanged), `'-'` (only in `old`) or `'+'`
+/// (only in `new`), in display order.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            out.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(('-', old[i]));
+            i += 1;
+        } else {
+            out.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    out.extend(old[i..n].iter().map(|line| ('-', *line)));
+    out.extend(new[j..m].iter().map(|line| ('+', *line)));
+    out
+}
+
+/// Renders a compact, colored line diff between `expected` and `actual`,
+/// suitable for printing alongside a snapshot mismatch.
+pub fn render(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    let mut out = String::new();
+    for (tag, line) in diff_lines(&old, &new) {
+        match tag {
+            '-' => out.push_str(&format!("\x1b[31m-{line}\x1b[0m\n")),
+            '+' => out.push_str(&format!("\x1b[32m+{line}\x1b[0m\n")),
+            _ => out.push_str(&format!(" {line}\n")),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_input_has_no_markers() {
+        let out = render("a\nb\nc\n", "a\nb\nc\n");
+        assert_eq!(out, " a\n b\n c\n");
+    }
+
+    #[test]
+    fn pure_insert_marks_only_added_lines() {
+        let out = render("a\nc\n", "a\nb\nc\n");
+        assert_eq!(out, " a\n\x1b[32m+b\x1b[0m\n c\n");
+    }
+
+    #[test]
+    fn pure_delete_marks_only_removed_lines() {
+        let out = render("a\nb\nc\n", "a\nc\n");
+        assert_eq!(out, " a\n\x1b[31m-b\x1b[0m\n c\n");
+    }
+
+    #[test]
+    fn replace_marks_old_line_removed_and_new_line_added() {
+        let out = render("a\nb\nc\n", "a\nx\nc\n");
+        assert_eq!(out, " a\n\x1b[31m-b\x1b[0m\n\x1b[32m+x\x1b[0m\n c\n");
+    }
+}
@@ -0,0 +1,149 @@
+//! Shared helpers for the generated integration tests under `tests/generated`.
+
+mod cache;
+mod diff;
+mod snapshot;
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use camino::Utf8PathBuf;
+use tempdir::TempDir;
+use wait_timeout::ChildExt;
+use xshell::{cmd, Shell};
+
+pub use cache::compile_cached;
+
+const FIXTURES_DIR: &str = "tests/fixtures";
+
+/// Default wall-clock bound for a single [`invoke`] call.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A `jco.js` subcommand a generated test can exercise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JcoCommand {
+    Run,
+    Transpile,
+    Compose,
+}
+
+impl JcoCommand {
+    fn as_str(self) -> &'static str {
+        match self {
+            JcoCommand::Run => "run",
+            JcoCommand::Transpile => "transpile",
+            JcoCommand::Compose => "compose",
+        }
+    }
+}
+
+/// Compiles the WASI fixture named `file_name` (found at
+/// `tests/fixtures/{file_name}.wat`) into a component, writing the result
+/// into `tempdir`, and returns the path to the compiled component.
+pub fn compile(sh: &Shell, tempdir: &TempDir, file_name: &str) -> Result<Utf8PathBuf> {
+    let fixture = Utf8PathBuf::from(FIXTURES_DIR).join(format!("{file_name}.wat"));
+    let wasi_file = Utf8PathBuf::from_path_buf(tempdir.path().join(format!("{file_name}.wasm")))
+        .expect("tempdir path is not valid UTF-8");
+
+    cmd!(sh, "wasm-tools component new {fixture} -o {wasi_file}")
+        .run()
+        .with_context(|| format!("failed to compile fixture `{file_name}`"))?;
+
+    Ok(wasi_file)
+}
+
+/// Runs `./src/jco.js {command} {args} {wasi_file}`, killing it and failing
+/// with a clear message if it's still running after `timeout`, and
+/// otherwise asserting its exit code matches `expected_exit_code`. When
+/// `snapshot` is set, also compares stdout/stderr against the
+/// `{file_name}.stdout`/`{file_name}.stderr` files committed next to the
+/// fixture (an absent file means "expect empty output"); set
+/// `UPDATE_EXPECT=1` to bless the committed files in place instead of
+/// failing.
+#[allow(clippy::too_many_arguments)]
+pub fn invoke(
+    sh: &Shell,
+    file_name: &str,
+    command: JcoCommand,
+    args: &[&str],
+    wasi_file: &Utf8PathBuf,
+    expected_exit_code: i32,
+    snapshot: bool,
+    timeout: Duration,
+) -> Result<()> {
+    let subcommand = command.as_str();
+
+    let mut child = Command::new("./src/jco.js")
+        .current_dir(sh.current_dir())
+        .arg(subcommand)
+        .args(args)
+        .arg(wasi_file.as_str())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to spawn `./src/jco.js {subcommand}` for `{file_name}`"))?;
+
+    // Drain stdout/stderr on their own threads while we wait, so a chatty
+    // child can't deadlock by filling its pipe buffer before exiting.
+    let mut stdout_pipe = child.stdout.take().expect("piped stdout");
+    let mut stderr_pipe = child.stderr.take().expect("piped stderr");
+    let stdout_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout_pipe.read_to_end(&mut buf);
+        buf
+    });
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr_pipe.read_to_end(&mut buf);
+        buf
+    });
+
+    let status = child
+        .wait_timeout(timeout)
+        .with_context(|| format!("failed to wait on `./src/jco.js {subcommand}` for `{file_name}`"))?;
+    let status = match status {
+        Some(status) => status,
+        None => {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!(
+                "`./src/jco.js {subcommand} ... {wasi_file}` for `{file_name}` exceeded {}s",
+                timeout.as_secs()
+            );
+        }
+    };
+
+    let stdout = stdout_reader.join().expect("stdout reader thread panicked");
+    let stderr = stderr_reader.join().expect("stderr reader thread panicked");
+
+    let actual_exit_code = status.code().unwrap_or(-1);
+    if actual_exit_code != expected_exit_code {
+        bail!(
+            "`./src/jco.js {subcommand} ... {wasi_file}` for `{file_name}` exited with \
+{actual_exit_code}, expected {expected_exit_code}"
+        );
+    }
+
+    if snapshot {
+        let fixtures_dir = Utf8PathBuf::from(FIXTURES_DIR);
+        snapshot::check(
+            fixtures_dir.join(format!("{file_name}.stdout")).as_std_path(),
+            &String::from_utf8_lossy(&stdout),
+        )?;
+        snapshot::check(
+            fixtures_dir.join(format!("{file_name}.stderr")).as_std_path(),
+            &String::from_utf8_lossy(&stderr),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Runs the compiled `wasi_file` under `./src/jco.js run`, expecting a clean
+/// exit and snapshotting its stdout/stderr. A thin convenience wrapper
+/// around [`invoke`] for the common case.
+pub fn run_and_snapshot(sh: &Shell, file_name: &str, wasi_file: &Utf8PathBuf) -> Result<()> {
+    invoke(sh, file_name, JcoCommand::Run, &[], wasi_file, 0, true, DEFAULT_TIMEOUT)
+}
@@ -0,0 +1,86 @@
+//! Comparing command output against a committed snapshot file, with a
+//! one-command bless workflow via `UPDATE_EXPECT=1`.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::diff;
+
+/// Compares `actual` against the contents of `path` (treated as empty if
+/// `path` doesn't exist yet). If they differ and `UPDATE_EXPECT=1` is set,
+/// `path` is overwritten with `actual`; otherwise panics with a line diff.
+pub fn check(path: &Path, actual: &str) -> Result<()> {
+    let expected = fs::read_to_string(path).unwrap_or_default();
+    if normalize(&expected) == normalize(actual) {
+        return Ok(());
+    }
+
+    if env::var_os("UPDATE_EXPECT").is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory {}", parent.display()))?;
+        }
+        fs::write(path, actual)
+            .with_context(|| format!("failed to bless {}", path.display()))?;
+        return Ok(());
+    }
+
+    panic!(
+        "`{}` does not match the actual output, re-run with `UPDATE_EXPECT=1` to bless it:\n{}",
+        path.display(),
+        diff::render(&expected, actual)
+    );
+}
+
+fn normalize(s: &str) -> String {
+    s.replace("\r\n", "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempdir::TempDir;
+
+    // `check` reads the process-global UPDATE_EXPECT var, so any test here
+    // that sets it must hold this lock for the duration, or it could leak
+    // into a concurrently-running test in this module that doesn't expect it.
+    static UPDATE_EXPECT_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn missing_file_is_treated_as_empty_and_panics_on_nonempty_actual() {
+        let _guard = UPDATE_EXPECT_LOCK.lock().unwrap();
+        let dir = TempDir::new("snapshot-test").unwrap();
+        let path = dir.path().join("missing.stdout");
+
+        let result = std::panic::catch_unwind(|| check(&path, "hello\n"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn matching_file_passes_without_touching_it() {
+        let dir = TempDir::new("snapshot-test").unwrap();
+        let path = dir.path().join("matches.stdout");
+        fs::write(&path, "hello\n").unwrap();
+
+        check(&path, "hello\n").unwrap();
+    }
+
+    #[test]
+    fn update_expect_blesses_a_mismatched_file_in_place() {
+        let _guard = UPDATE_EXPECT_LOCK.lock().unwrap();
+        let dir = TempDir::new("snapshot-test").unwrap();
+        let path = dir.path().join("stale.stdout");
+        fs::write(&path, "old\n").unwrap();
+
+        env::set_var("UPDATE_EXPECT", "1");
+        let result = check(&path, "new\n");
+        env::remove_var("UPDATE_EXPECT");
+
+        result.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+    }
+}
@@ -0,0 +1,11 @@
+//! Guards against `tests/generated.rs` and `tests/generated/*.rs` drifting
+//! away from `cargo xtask generate tests`; see `xtask::codegen`.
+
+use xtask::codegen::{generate_tests, Mode};
+
+#[test]
+fn generated_tests_are_up_to_date() {
+    generate_tests(Mode::Verify).expect(
+        "tests/generated.rs and tests/generated/*.rs do not match `cargo xtask generate tests`",
+    );
+}
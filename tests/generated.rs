@@ -0,0 +1,5 @@
+//! This file has been auto-generated, please do not modify manually
+//! To regenerate this file re-run `cargo xtask generate tests` from the project root
+
+#[path = "generated/api_time.rs"]
+mod api_time;
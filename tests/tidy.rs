@@ -0,0 +1,7 @@
+//! Guards the generated test tree against manual edits and stray markers;
+//! see `xtask::tidy`.
+
+#[test]
+fn generated_tests_are_tidy() {
+    xtask::tidy::tidy().expect("`cargo xtask tidy` found a problem with tests/generated");
+}
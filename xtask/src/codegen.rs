@@ -0,0 +1,200 @@
+//! Generates the integration test suite under `tests/generated.rs` and
+//! `tests/generated/*.rs` as a pure projection of the manifest at
+//! `tests/fixtures/manifest.toml` (see `crate::manifest`).
+//!
+//! Every rendered file carries a "do not modify manually" banner; this
+//! module is the only thing that is allowed to produce that content, and
+//! `Mode::Verify` is how `cargo test` checks that nobody has hand-edited it
+//! or let it drift out of sync with the manifest.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::manifest::{self, Case, Invocation};
+
+/// Whether `generate_tests` should write its output to disk or merely check
+/// that the on-disk output already matches what would be written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Overwrite the on-disk files with freshly rendered contents.
+    Overwrite,
+    /// Render in-memory and fail if it doesn't match what's on disk.
+    Verify,
+}
+
+pub(crate) const BANNER: &str = "//! This file has been auto-generated, please do not modify manually\n\
+//! To regenerate this file re-run `cargo xtask generate tests` from the project root\n";
+
+/// Renders `tests/generated.rs` and one `tests/generated/{fixture}.rs` per
+/// case in the manifest, either writing them to disk or verifying that the
+/// files already on disk match.
+pub fn generate_tests(mode: Mode) -> Result<()> {
+    let tests_dir = project_root()?.join("tests");
+    let generated_dir = tests_dir.join("generated");
+    let manifest = manifest::load(&tests_dir.join("fixtures").join("manifest.toml"))?;
+
+    update(&tests_dir.join("generated.rs"), &render_aggregator(&manifest.case), mode)?;
+
+    for case in &manifest.case {
+        update(
+            &generated_dir.join(format!("{}.rs", case.fixture)),
+            &render_case(case),
+            mode,
+        )?;
+    }
+
+    check_for_stray_files(&generated_dir, &manifest.case, mode)?;
+
+    Ok(())
+}
+
+pub(crate) fn project_root() -> Result<PathBuf> {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .map(Path::to_path_buf)
+        .context("xtask crate has no parent directory")
+}
+
+fn render_aggregator(cases: &[Case]) -> String {
+    let mut out = String::new();
+    out.push_str(BANNER);
+    out.push('\n');
+    for case in cases {
+        let fixture = &case.fixture;
+        out.push_str(&format!("#[path = \"generated/{fixture}.rs\"]\nmod {fixture};\n"));
+    }
+    out
+}
+
+fn render_case(case: &Case) -> String {
+    let mut out = String::new();
+    out.push_str(BANNER);
+    out.push_str("\nuse xshell::Shell;\n");
+    for invocation in &case.invocation {
+        out.push_str(&render_test_fn(&case.fixture, invocation));
+    }
+    out
+}
+
+fn render_test_fn(fixture: &str, invocation: &Invocation) -> String {
+    let fn_name = format!("{fixture}_{}", invocation.command.as_str());
+
+    // `compile_cached` gives this fixture's component its own uniquely
+    // named temp dir the first time any of its cases needs it, and shares
+    // the result with the other invocations of the same fixture.
+    let call = if is_plain_run_and_snapshot(invocation) {
+        "test_utils::run_and_snapshot(&sh, file_name, &wasi_file)?;".to_string()
+    } else {
+        let args = invocation
+            .args
+            .iter()
+            .map(|arg| format!("{arg:?}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let variant = invocation.command.variant_name();
+        let exit_code = invocation.exit_code;
+        let snapshot = invocation.snapshot;
+        let timeout = match invocation.timeout_secs {
+            Some(secs) => format!("std::time::Duration::from_secs({secs})"),
+            None => "test_utils::DEFAULT_TIMEOUT".to_string(),
+        };
+        format!(
+            "let args: &[&str] = &[{args}];\n    test_utils::invoke(&sh, file_name, test_utils::JcoCommand::{variant}, args, &wasi_file, {exit_code}, {snapshot}, {timeout})?;"
+        )
+    };
+
+    format!(
+        "\n#[test]\nfn {fn_name}() -> anyhow::Result<()> {{\n    let sh = Shell::new()?;\n    let file_name = \"{fixture}\";\n    let wasi_file = test_utils::compile_cached(&sh, file_name)?;\n    {call}\n    Ok(())\n}}\n"
+    )
+}
+
+/// Whether `invocation` is exactly the case `test_utils::run_and_snapshot`
+/// covers: a plain `jco run` with no extra flags, a clean exit, a snapshot
+/// check, and no per-case timeout override. Using the helper here instead of
+/// `invoke` directly keeps it from being unused dead API for the common case
+/// it was written for.
+fn is_plain_run_and_snapshot(invocation: &Invocation) -> bool {
+    matches!(invocation.command, manifest::Command::Run)
+        && invocation.args.is_empty()
+        && invocation.exit_code == 0
+        && invocation.snapshot
+        && invocation.timeout_secs.is_none()
+}
+
+/// Writes `contents` to `path` in `Overwrite` mode, or fails with an
+/// actionable message if `path` doesn't already hold `contents` in `Verify`
+/// mode. Line endings are normalized before comparing so checking out the
+/// repo on Windows doesn't trip the check.
+fn update(path: &Path, contents: &str, mode: Mode) -> Result<()> {
+    let contents = normalize_line_endings(contents);
+    match mode {
+        Mode::Overwrite => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create directory {}", parent.display()))?;
+            }
+            fs::write(path, contents)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        Mode::Verify => {
+            let on_disk = fs::read_to_string(path)
+                .map(|s| normalize_line_endings(&s))
+                .unwrap_or_default();
+            if on_disk != contents {
+                let mut message = format!(
+                    "`{}` was not up-to-date, re-run `cargo xtask generate tests`",
+                    path.display()
+                );
+                if env::var_os("CI").is_some() {
+                    message.push_str(
+                        "\nhint: run `cargo xtask generate tests` locally and commit the result",
+                    );
+                }
+                bail!(message);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn normalize_line_endings(s: &str) -> String {
+    s.replace("\r\n", "\n")
+}
+
+/// In `Verify` mode, reports any fixture that no longer has a generated file
+/// on disk ("missing") as well as any generated file that doesn't correspond
+/// to a fixture anymore ("extra"), since `update` alone wouldn't catch a
+/// leftover file from a fixture that was removed.
+fn check_for_stray_files(generated_dir: &Path, cases: &[Case], mode: Mode) -> Result<()> {
+    if mode != Mode::Verify {
+        return Ok(());
+    }
+
+    let expected: BTreeSet<String> = cases.iter().map(|case| format!("{}.rs", case.fixture)).collect();
+    let mut on_disk = BTreeSet::new();
+    if generated_dir.exists() {
+        for entry in fs::read_dir(generated_dir)
+            .with_context(|| format!("failed to read {}", generated_dir.display()))?
+        {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                on_disk.insert(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    let missing: Vec<_> = expected.difference(&on_disk).collect();
+    let extra: Vec<_> = on_disk.difference(&expected).collect();
+    if !missing.is_empty() || !extra.is_empty() {
+        bail!(
+            "tests/generated/ is out of sync with the fixture list (missing: {missing:?}, extra: {extra:?}); \
+re-run `cargo xtask generate tests`"
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+//! Support code for `cargo xtask`, factored into a library so the test
+//! suite can reuse the codegen logic to check that its generated files are
+//! up-to-date (see `codegen::Mode::Verify`).
+
+pub mod codegen;
+pub mod manifest;
+pub mod tidy;
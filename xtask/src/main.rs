@@ -0,0 +1,14 @@
+//! `cargo xtask` entry point. See `xtask::codegen` for what `generate tests`
+//! actually does.
+
+use anyhow::bail;
+use xtask::codegen::{self, Mode};
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["generate", "tests"] => codegen::generate_tests(Mode::Overwrite),
+        ["tidy"] => xtask::tidy::tidy(),
+        _ => bail!("usage: cargo xtask generate tests | cargo xtask tidy"),
+    }
+}
@@ -0,0 +1,141 @@
+//! The declarative manifest at `tests/fixtures/manifest.toml` that drives
+//! `cargo xtask generate tests`: for each fixture, which `jco` subcommands
+//! to exercise, with what flags, and what result to expect.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Top-level manifest: one entry per fixture.
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    pub case: Vec<Case>,
+}
+
+/// A single fixture and the `jco` invocations to generate a test for.
+#[derive(Debug, Deserialize)]
+pub struct Case {
+    pub fixture: String,
+    pub invocation: Vec<Invocation>,
+}
+
+/// One `jco` subcommand invocation to exercise against `Case::fixture`,
+/// rendered as its own `#[test] fn`.
+#[derive(Debug, Deserialize)]
+pub struct Invocation {
+    pub command: Command,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub exit_code: i32,
+    #[serde(default)]
+    pub snapshot: bool,
+    /// Overrides `test_utils::DEFAULT_TIMEOUT` for this invocation.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// `jco` subcommands a generated test can exercise.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Command {
+    Run,
+    Transpile,
+    Compose,
+}
+
+impl Command {
+    /// The `jco.js` subcommand name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Command::Run => "run",
+            Command::Transpile => "transpile",
+            Command::Compose => "compose",
+        }
+    }
+
+    /// The matching `test_utils::JcoCommand` variant name, for codegen.
+    pub fn variant_name(self) -> &'static str {
+        match self {
+            Command::Run => "Run",
+            Command::Transpile => "Transpile",
+            Command::Compose => "Compose",
+        }
+    }
+}
+
+/// Reads and parses the manifest at `path`.
+pub fn load(path: &Path) -> Result<Manifest> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read manifest {}", path.display()))?;
+    let manifest: Manifest = toml::from_str(&text)
+        .with_context(|| format!("failed to parse manifest {}", path.display()))?;
+    validate(&manifest, path)?;
+    Ok(manifest)
+}
+
+/// Every generated test fn is named `{fixture}_{command}` (see
+/// `xtask::codegen::render_test_fn`), so two invocations of the same command
+/// against the same fixture would render two identically-named `fn`s and
+/// only fail much later with a raw `E0428` from `cargo build`. Catch that
+/// here instead, with a message that names the offending fixture/command.
+fn validate(manifest: &Manifest, path: &Path) -> Result<()> {
+    let mut seen = HashSet::new();
+    for case in &manifest.case {
+        for invocation in &case.invocation {
+            if !seen.insert((case.fixture.as_str(), invocation.command.as_str())) {
+                bail!(
+                    "{}: fixture `{}` has more than one `{}` invocation, but the generated test \
+fn is named `{}_{}` for all of them; give each a distinct command or split the fixture",
+                    path.display(),
+                    case.fixture,
+                    invocation.command.as_str(),
+                    case.fixture,
+                    invocation.command.as_str(),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invocation(command: Command) -> Invocation {
+        Invocation {
+            command,
+            args: Vec::new(),
+            exit_code: 0,
+            snapshot: false,
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn distinct_commands_on_the_same_fixture_are_fine() {
+        let manifest = Manifest {
+            case: vec![Case {
+                fixture: "api_time".to_string(),
+                invocation: vec![invocation(Command::Run), invocation(Command::Transpile)],
+            }],
+        };
+        validate(&manifest, Path::new("manifest.toml")).unwrap();
+    }
+
+    #[test]
+    fn duplicate_command_on_the_same_fixture_is_rejected() {
+        let manifest = Manifest {
+            case: vec![Case {
+                fixture: "api_time".to_string(),
+                invocation: vec![invocation(Command::Run), invocation(Command::Run)],
+            }],
+        };
+        let err = validate(&manifest, Path::new("manifest.toml")).unwrap_err();
+        assert!(err.to_string().contains("api_time_run"), "{err}");
+    }
+}
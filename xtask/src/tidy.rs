@@ -0,0 +1,73 @@
+//! `cargo xtask tidy`: guards the generated test tree against manual edits
+//! and stray markers, on top of the freshness check `codegen::Mode::Verify`
+//! already provides.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::codegen::{self, Mode, BANNER};
+
+/// Markers that have no business surviving in a file nobody is supposed to
+/// hand-edit.
+const FORBIDDEN_MARKERS: &[&str] = &["TODO", "FIXME"];
+
+/// Runs every tidy check over `tests/generated.rs` and `tests/generated/*`,
+/// failing on the first violation with the offending path and rule.
+///
+/// The banner/marker scan runs *before* the full-content `Verify` round-trip:
+/// both checks are symptoms of the same "don't hand-edit generated files"
+/// rule, but the round-trip comparison fails on *any* difference from the
+/// generator's output, banner and markers included — running it first would
+/// always win the race and report the generic "was not up-to-date" message
+/// instead of the specific rule this file actually broke.
+pub fn tidy() -> Result<()> {
+    let tests_dir = codegen::project_root()?.join("tests");
+    for path in generated_files(&tests_dir)? {
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        if !contents.starts_with(BANNER) {
+            bail!(
+                "{}: missing the \"do not modify manually\" banner",
+                path.display()
+            );
+        }
+
+        for marker in FORBIDDEN_MARKERS {
+            if contents.contains(marker) {
+                bail!(
+                    "{}: contains a `{marker}` marker, which isn't allowed in generated files",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    // Reuses the `Verify` comparison: every generated file's body must
+    // round-trip byte-identical through the generator. Runs last, since it's
+    // the catch-all for drift that isn't a banner/marker violation (e.g. the
+    // manifest changed but the file wasn't regenerated).
+    codegen::generate_tests(Mode::Verify)?;
+
+    Ok(())
+}
+
+fn generated_files(tests_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![tests_dir.join("generated.rs")];
+
+    let generated_dir = tests_dir.join("generated");
+    if generated_dir.exists() {
+        for entry in fs::read_dir(&generated_dir)
+            .with_context(|| format!("failed to read {}", generated_dir.display()))?
+        {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                files.push(entry.path());
+            }
+        }
+    }
+
+    Ok(files)
+}